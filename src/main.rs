@@ -1,48 +1,285 @@
 use std::{
     env::args,
     error::Error,
-    fs::{File, OpenOptions},
-    io::{Seek, SeekFrom, Write},
-    path::PathBuf,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
 };
 
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use pulldown_cmark::{html, Parser};
 use rand::Rng;
+use rustyline::{error::ReadlineError, DefaultEditor};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
 struct Note {
     content: String,
     id: String,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 #[derive(Debug)]
 enum Action {
-    List,
-    Get { id: String },
-    Add { content: String },
-    Patch { id: String, content: String },
-    Delete { id: String },
+    List {
+        tag: Option<String>,
+    },
+    Get {
+        id: String,
+    },
+    Add {
+        content: String,
+        tags: Vec<String>,
+    },
+    Patch {
+        id: String,
+        content: String,
+        tags: Vec<String>,
+    },
+    Delete {
+        id: String,
+    },
+    Search {
+        query: String,
+    },
+    Render {
+        output: Option<PathBuf>,
+    },
+    Convert {
+        output: PathBuf,
+    },
+    Repl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Result<Format, Box<dyn Error>> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("json") => Ok(Format::Json),
+            Some("yaml") | Some("yml") => Ok(Format::Yaml),
+            Some("toml") => Ok(Format::Toml),
+            _ => Err("file must end in .json, .yaml/.yml, or .toml (optionally followed by .lz)".into()),
+        }
+    }
+}
+
+fn resolve_format_and_compression(path: &Path) -> Result<(Format, bool), Box<dyn Error>> {
+    let is_lz = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .as_deref()
+        == Some("lz");
+    let format_path = if is_lz {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    };
+
+    Ok((Format::from_path(&format_path)?, is_lz))
+}
+
+#[derive(Serialize, Deserialize)]
+struct NotesDocument {
+    notes: Vec<Note>,
 }
 
 #[derive(Debug)]
 struct Args {
     action: Action,
     file: File,
+    path: PathBuf,
+    format: Format,
+    compressed: bool,
 }
 
-fn read_notes(file: &File) -> Result<Vec<Note>, Box<dyn Error>> {
-    Ok(serde_json::from_reader(file)?)
+fn serialize_notes(notes: &Vec<Note>, format: Format) -> Result<Vec<u8>, Box<dyn Error>> {
+    match format {
+        Format::Json => Ok(serde_json::to_vec(notes)?),
+        Format::Yaml => Ok(serde_yaml::to_string(notes)?.into_bytes()),
+        Format::Toml => {
+            let document = NotesDocument {
+                notes: notes.clone(),
+            };
+            Ok(toml::to_string_pretty(&document)?.into_bytes())
+        }
+    }
 }
 
-fn write_notes(notes: &Vec<Note>, file: &mut File) -> Result<(), Box<dyn Error>> {
-    file.seek(SeekFrom::Start(0))?;
-    file.set_len(0)?;
-    file.write_all(serde_json::to_string(&notes)?.as_bytes())?;
-    Ok(())
+fn deserialize_notes(bytes: &[u8], format: Format) -> Result<Vec<Note>, Box<dyn Error>> {
+    match format {
+        Format::Json => Ok(serde_json::from_slice(bytes)?),
+        Format::Yaml => Ok(serde_yaml::from_slice(bytes)?),
+        Format::Toml => {
+            let document: NotesDocument = toml::from_str(std::str::from_utf8(bytes)?)?;
+            Ok(document.notes)
+        }
+    }
+}
+
+fn compress_bytes(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress_bytes(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut decoder = GzDecoder::new(bytes);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+fn read_notes(file: &File, format: Format, compressed: bool) -> Result<Vec<Note>, Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    let mut reader = file;
+    reader.read_to_end(&mut bytes)?;
+
+    let bytes = if compressed {
+        decompress_bytes(&bytes)?
+    } else {
+        bytes
+    };
+
+    deserialize_notes(&bytes, format)
 }
 
-fn format_note(Note { content, id }: &Note) -> String {
-    format!("{id} -> {content}")
+fn write_notes(
+    notes: &Vec<Note>,
+    path: &Path,
+    format: Format,
+    compressed: bool,
+) -> Result<(), Box<dyn Error>> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| "notes path must have a file name")?;
+
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(format!(".tmp-{}", generate_id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write_result = (|| -> Result<(), Box<dyn Error>> {
+        let mut bytes = serialize_notes(notes, format)?;
+        if compressed {
+            bytes = compress_bytes(&bytes)?;
+        }
+
+        let mut tmp_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+
+        tmp_file.write_all(&bytes)?;
+        tmp_file.flush()?;
+        tmp_file.sync_all()?;
+
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    })();
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    write_result
+}
+
+fn format_note(Note { content, id, tags }: &Note) -> String {
+    if tags.is_empty() {
+        format!("{id} -> {content}")
+    } else {
+        format!("{id} -> {content} #{}", tags.join(" #"))
+    }
+}
+
+fn render_notes_html(notes: &[Note]) -> String {
+    let mut html_out = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Notes</title>\n</head>\n<body>\n",
+    );
+
+    for note in notes {
+        html_out.push_str(&format!("<article id=\"{}\">\n", html_escape(&note.id)));
+        html_out.push_str(&format!("<h2>{}</h2>\n", html_escape(&note.id)));
+
+        if !note.tags.is_empty() {
+            html_out.push_str(&format!(
+                "<p class=\"tags\">{}</p>\n",
+                html_escape(&format_tags(&note.tags))
+            ));
+        }
+
+        // Note content is rendered as trusted Markdown/HTML, not sanitized.
+        let parser = Parser::new(&note.content);
+        html::push_html(&mut html_out, parser);
+
+        html_out.push_str("</article>\n");
+    }
+
+    html_out.push_str("</body>\n</html>\n");
+    html_out
+}
+
+fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!("#{}", tags.join(" #"))
+    }
+}
+
+fn html_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn parse_content_and_tags(tokens: &[String]) -> (String, Vec<String>) {
+    let mut tags = Vec::new();
+    let mut content_words = Vec::new();
+    let mut iter = tokens.iter();
+
+    while let Some(token) = iter.next() {
+        if token == "--tag" {
+            if let Some(value) = iter.next() {
+                tags.push(value.clone());
+            }
+        } else {
+            content_words.push(token.clone());
+        }
+    }
+
+    // Only trailing `#tag` tokens are tags, so `#` elsewhere is kept as content.
+    let mut trailing_tags = Vec::new();
+    while let Some(last) = content_words.last() {
+        match last.strip_prefix('#') {
+            Some(tag) => {
+                trailing_tags.push(tag.to_string());
+                content_words.pop();
+            }
+            None => break,
+        }
+    }
+    trailing_tags.reverse();
+    tags.extend(trailing_tags);
+
+    (content_words.join(" "), tags)
 }
 
 fn generate_id() -> String {
@@ -61,23 +298,190 @@ fn generate_id() -> String {
     id
 }
 
+fn history_path() -> Option<PathBuf> {
+    let mut dir = dirs::data_dir()?;
+    dir.push("note-file-manager");
+    std::fs::create_dir_all(&dir).ok()?;
+    dir.push("history.txt");
+    Some(dir)
+}
+
+fn run_repl(
+    notes: &mut Vec<Note>,
+    path: &Path,
+    format: Format,
+    compressed: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut editor = DefaultEditor::new()?;
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline("note> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        editor.add_history_entry(trimmed)?;
+
+        let mut parts = trimmed.splitn(2, ' ');
+        let command = parts.next().unwrap_or_default();
+        let rest = parts.next().unwrap_or("").trim();
+
+        match command {
+            "list" => {
+                let tag = rest
+                    .strip_prefix("--tag")
+                    .map(|rest| rest.trim().to_string());
+                let filtered: Vec<&Note> = notes
+                    .iter()
+                    .filter(|note| match &tag {
+                        Some(tag) => note.tags.iter().any(|t| t == tag),
+                        None => true,
+                    })
+                    .collect();
+
+                if filtered.is_empty() {
+                    println!("No notes found");
+                } else {
+                    for note in filtered {
+                        println!("{}", format_note(note));
+                    }
+                }
+            }
+            "get" => match notes.iter().find(|note| note.id == rest) {
+                Some(note) => println!("{}", format_note(note)),
+                None => println!("note not found"),
+            },
+            "search" => {
+                let query = rest.to_lowercase();
+                let matches: Vec<&Note> = notes
+                    .iter()
+                    .filter(|note| {
+                        note.content.to_lowercase().contains(&query)
+                            || note
+                                .tags
+                                .iter()
+                                .any(|tag| tag.to_lowercase().contains(&query))
+                    })
+                    .collect();
+
+                if matches.is_empty() {
+                    println!("No notes found");
+                } else {
+                    for note in matches {
+                        println!("{}", format_note(note));
+                    }
+                }
+            }
+            "add" => {
+                let tokens: Vec<String> = rest.split_whitespace().map(String::from).collect();
+                let (content, tags) = parse_content_and_tags(&tokens);
+
+                if content.is_empty() {
+                    println!("content must be provided");
+                    continue;
+                }
+
+                let note = Note {
+                    id: generate_id(),
+                    content,
+                    tags,
+                };
+                println!("{}", format_note(&note));
+                notes.push(note);
+                write_notes(notes, path, format, compressed)?;
+            }
+            "patch" => {
+                let mut parts = rest.splitn(2, ' ');
+                let id = parts.next().unwrap_or_default();
+                let rest = parts.next().unwrap_or_default();
+
+                let tokens: Vec<String> = rest.split_whitespace().map(String::from).collect();
+                let (content, tags) = parse_content_and_tags(&tokens);
+
+                if content.is_empty() {
+                    println!("content must be provided");
+                    continue;
+                }
+
+                match notes.iter_mut().find(|note| note.id == id) {
+                    Some(note) => {
+                        note.content = content;
+                        if !tags.is_empty() {
+                            note.tags = tags;
+                        }
+                        println!("{}", format_note(note));
+                    }
+                    None => {
+                        println!("note not found");
+                        continue;
+                    }
+                }
+                write_notes(notes, path, format, compressed)?;
+            }
+            "delete" => {
+                let initial_len = notes.len();
+                notes.retain(|note| note.id != rest);
+
+                if notes.len() == initial_len {
+                    println!("note not found");
+                    continue;
+                }
+                write_notes(notes, path, format, compressed)?;
+            }
+            "save" => write_notes(notes, path, format, compressed)?,
+            "exit" | "quit" => break,
+            other => println!("unknown command: {other}"),
+        }
+    }
+
+    write_notes(notes, path, format, compressed)?;
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+
+    Ok(())
+}
+
 fn parse_args() -> Result<Args, Box<dyn Error>> {
-    let args: Vec<String> = args().collect();
+    let raw_args: Vec<String> = args().collect();
+    let compress_flag = raw_args.iter().any(|arg| arg == "--compress");
+    let args: Vec<String> = raw_args
+        .into_iter()
+        .filter(|arg| arg != "--compress")
+        .collect();
 
     let file_path = match args.get(1) {
         Some(path) => PathBuf::from(path),
         None => return Err("file path must be provided".into()),
     };
 
+    let (format, is_lz) = resolve_format_and_compression(&file_path)?;
+    let compressed = compress_flag || is_lz;
+
     let mut file = OpenOptions::new()
         .create(true)
         .read(true)
         .write(true)
-        .open(file_path)?;
+        .open(&file_path)?;
 
     if let Ok(metadata) = file.metadata() {
         if metadata.len() <= 0 {
-            file.write_all(b"[]")?;
+            let mut bytes = serialize_notes(&Vec::new(), format)?;
+            if compressed {
+                bytes = compress_bytes(&bytes)?;
+            }
+            file.write_all(&bytes)?;
             file.seek(SeekFrom::Start(0))?;
         }
     };
@@ -87,51 +491,99 @@ fn parse_args() -> Result<Args, Box<dyn Error>> {
         .ok_or_else(|| "action must be provided")?
         .as_str()
     {
-        "list" => Action::List,
+        "list" => {
+            let tag = match args.get(3).map(String::as_str) {
+                Some("--tag") => Some(
+                    args.get(4)
+                        .ok_or_else(|| "tag must be provided")?
+                        .to_string(),
+                ),
+                _ => None,
+            };
+            Action::List { tag }
+        }
         "get" => Action::Get {
             id: args
                 .get(3)
                 .ok_or_else(|| "id must be provided")?
                 .to_string(),
         },
-        "add" => Action::Add {
-            content: args
+        "add" => {
+            let (content, tags) = parse_content_and_tags(args.get(3..).unwrap_or_default());
+            if content.is_empty() {
+                return Err("content must be provided".into());
+            }
+            Action::Add { content, tags }
+        }
+        "patch" => {
+            let id = args
                 .get(3)
-                .ok_or_else(|| "content must be provided")?
-                .to_string(),
-        },
-        "patch" => Action::Patch {
+                .ok_or_else(|| "id must be provided")?
+                .to_string();
+            let (content, tags) = parse_content_and_tags(args.get(4..).unwrap_or_default());
+            if content.is_empty() {
+                return Err("content must be provided".into());
+            }
+            Action::Patch { id, content, tags }
+        }
+        "delete" => Action::Delete {
             id: args
                 .get(3)
                 .ok_or_else(|| "id must be provided")?
                 .to_string(),
-            content: args
-                .get(4)
-                .ok_or_else(|| "content must be provided")?
-                .to_string(),
         },
-        "delete" => Action::Delete {
-            id: args
+        "search" => Action::Search {
+            query: args
                 .get(3)
-                .ok_or_else(|| "id must be provided")?
+                .ok_or_else(|| "query must be provided")?
                 .to_string(),
         },
+        "render" => Action::Render {
+            output: args.get(3).map(PathBuf::from),
+        },
+        "convert" => Action::Convert {
+            output: args
+                .get(3)
+                .map(PathBuf::from)
+                .ok_or_else(|| "output path must be provided")?,
+        },
+        "repl" => Action::Repl,
         _ => return Err("unknown action".into()),
     };
 
-    Ok(Args { file, action })
+    Ok(Args {
+        file,
+        path: file_path,
+        format,
+        compressed,
+        action,
+    })
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let Args { action, mut file } = parse_args()?;
+    let Args {
+        action,
+        file,
+        path,
+        format,
+        compressed,
+    } = parse_args()?;
 
     match action {
-        Action::List => {
-            let notes = read_notes(&file)?;
+        Action::List { tag } => {
+            let notes = read_notes(&file, format, compressed)?;
 
-            if notes.len() > 0 {
-                for note in notes {
-                    println!("{}", format_note(&note));
+            let filtered: Vec<&Note> = notes
+                .iter()
+                .filter(|note| match &tag {
+                    Some(tag) => note.tags.iter().any(|t| t == tag),
+                    None => true,
+                })
+                .collect();
+
+            if filtered.len() > 0 {
+                for note in filtered {
+                    println!("{}", format_note(note));
                 }
             } else {
                 println!("No notes found");
@@ -139,7 +591,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
 
         Action::Get { id } => {
-            let notes = read_notes(&file)?;
+            let notes = read_notes(&file, format, compressed)?;
 
             let note = notes
                 .iter()
@@ -149,36 +601,64 @@ fn main() -> Result<(), Box<dyn Error>> {
             println!("{}", format_note(&note));
         }
 
-        Action::Add { content } => {
-            let mut notes = read_notes(&file)?;
+        Action::Search { query } => {
+            let notes = read_notes(&file, format, compressed)?;
+            let query = query.to_lowercase();
+
+            let matches: Vec<&Note> = notes
+                .iter()
+                .filter(|note| {
+                    note.content.to_lowercase().contains(&query)
+                        || note
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase().contains(&query))
+                })
+                .collect();
+
+            if matches.len() > 0 {
+                for note in matches {
+                    println!("{}", format_note(note));
+                }
+            } else {
+                println!("No notes found");
+            }
+        }
+
+        Action::Add { content, tags } => {
+            let mut notes = read_notes(&file, format, compressed)?;
 
             let note = Note {
                 id: generate_id(),
                 content,
+                tags,
             };
 
             notes.push(note.clone());
 
-            write_notes(&notes, &mut file)?;
+            write_notes(&notes, &path, format, compressed)?;
 
             println!("{}", format_note(&note));
         }
 
-        Action::Patch { id, content } => {
-            let mut notes = read_notes(&file)?;
+        Action::Patch { id, content, tags } => {
+            let mut notes = read_notes(&file, format, compressed)?;
 
             if let Some(note) = notes.iter_mut().find(|note| note.id == id) {
                 note.content = content;
+                if !tags.is_empty() {
+                    note.tags = tags;
+                }
                 println!("{}", format_note(&note));
             } else {
                 return Err("note not found".into());
             }
 
-            write_notes(&notes, &mut file)?;
+            write_notes(&notes, &path, format, compressed)?;
         }
 
         Action::Delete { id } => {
-            let mut notes = read_notes(&file)?;
+            let mut notes = read_notes(&file, format, compressed)?;
 
             let initial_len = notes.len();
             notes.retain(|note| note.id != id);
@@ -187,9 +667,87 @@ fn main() -> Result<(), Box<dyn Error>> {
                 return Err("note not found".into());
             }
 
-            write_notes(&notes, &mut file)?;
+            write_notes(&notes, &path, format, compressed)?;
+        }
+
+        Action::Render { output } => {
+            let notes = read_notes(&file, format, compressed)?;
+            let html_out = render_notes_html(&notes);
+
+            match output {
+                Some(output_path) => fs::write(output_path, html_out)?,
+                None => println!("{html_out}"),
+            }
+        }
+
+        Action::Convert { output } => {
+            // Input and output compression are both derived solely from their own
+            // path extensions, so the global `--compress` flag never mis-drives
+            // the input read.
+            let (input_format, input_compressed) = resolve_format_and_compression(&path)?;
+            let notes = read_notes(&file, input_format, input_compressed)?;
+            let (output_format, output_compressed) = resolve_format_and_compression(&output)?;
+
+            write_notes(&notes, &output, output_format, output_compressed)?;
+        }
+
+        Action::Repl => {
+            let mut notes = read_notes(&file, format, compressed)?;
+            run_repl(&mut notes, &path, format, compressed)?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_notes() -> Vec<Note> {
+        vec![
+            Note {
+                id: "abc123".to_string(),
+                content: "first note".to_string(),
+                tags: vec!["work".to_string()],
+            },
+            Note {
+                id: "def456".to_string(),
+                content: "second note".to_string(),
+                tags: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn compress_then_decompress_round_trips() {
+        let notes = sample_notes();
+        let serialized = serialize_notes(&notes, Format::Json).unwrap();
+
+        let compressed = compress_bytes(&serialized).unwrap();
+        let decompressed = decompress_bytes(&compressed).unwrap();
+
+        assert_eq!(decompressed, serialized);
+    }
+
+    #[test]
+    fn write_and_read_compressed_notes_file_preserves_ids_and_content() {
+        let notes = sample_notes();
+        let path =
+            std::env::temp_dir().join(format!("note-file-manager-test-{}.json.lz", generate_id()));
+
+        write_notes(&notes, &path, Format::Json, true).unwrap();
+
+        let file = OpenOptions::new().read(true).open(&path).unwrap();
+        let read_back = read_notes(&file, Format::Json, true).unwrap();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read_back.len(), notes.len());
+        for (original, restored) in notes.iter().zip(read_back.iter()) {
+            assert_eq!(original.id, restored.id);
+            assert_eq!(original.content, restored.content);
+            assert_eq!(original.tags, restored.tags);
+        }
+    }
+}